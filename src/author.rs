@@ -1,6 +1,169 @@
-#[derive(Debug)]
+use std::fmt;
+
+#[derive(Debug, Clone)]
 pub struct Author {
     name: String,
     email: String,
-    role: String,
+    roles: Vec<String>,
+}
+
+impl Author {
+    /// Parses a team list item like `* John Doe <john@example.com> - Developer`.
+    /// The name can be quoted, as in `"John Doe" <john@example.com>`. The role
+    /// is optional and several roles can be given separated by commas.
+    /// Returns `None` when the line is not well formed.
+    pub fn parse(line: &str) -> Option<Author> {
+        let line = line.trim();
+        if !line.starts_with("* ") {
+            return None;
+        }
+        let line = line[2..].trim_start();
+
+        // a quoted name may contain the characters delimiting the email
+        let name_end =
+            if let Some(quoted) = line.strip_prefix('"') {
+                quoted.find('"')? + 2
+            } else {
+                0
+            };
+
+        let email_start = name_end + line[name_end..].find('<')?;
+        let email_end = email_start + line[email_start..].find('>')?;
+
+        let name = line[..email_start].trim();
+        let name =
+            if name_end > 0 {
+                name.strip_prefix('"')?.strip_suffix('"')?.trim()
+            } else {
+                name
+            };
+        let email = line[email_start + 1..email_end].trim();
+        let rest = line[email_end + 1..].trim();
+
+        if name.is_empty() || !email.contains('@') {
+            return None;
+        }
+
+        let roles =
+            if rest.is_empty() {
+                ""
+            } else {
+                rest.strip_prefix('-')?
+            };
+
+        Some(Author {
+            name: name.to_string(),
+            email: email.to_string(),
+            roles: roles
+                .split(',')
+                .map(|role| role.trim())
+                .filter(|role| !role.is_empty())
+                .map(|role| role.to_string())
+                .collect(),
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    /// The email with its domain lowercased. The local part is kept as it is
+    /// because some mail systems treat it as case-sensitive.
+    pub fn normalized_email(&self) -> String {
+        match self.email.rfind('@') {
+            Some(index) => format!("{}{}", &self.email[..index], self.email[index..].to_lowercase()),
+            None => self.email.clone(),
+        }
+    }
+
+    /// The first role of the team member.
+    pub fn role(&self) -> Option<&str> {
+        self.roles.first().map(|role| role.as_str())
+    }
+
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|r| r.eq_ignore_ascii_case(role.trim()))
+    }
+}
+
+impl fmt::Display for Author {
+    /// Formats the team member as a team list item.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.name.contains(['<', '>']) {
+            write!(f, "* \"{}\" <{}>", self.name, self.email)?;
+        } else {
+            write!(f, "* {} <{}>", self.name, self.email)?;
+        }
+        if !self.roles.is_empty() {
+            write!(f, " - {}", self.roles.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the team members whose normalized email is the same as the email
+/// of a member listed before them.
+pub fn find_duplicates(members: &[Author]) -> Vec<&Author> {
+    members
+        .iter()
+        .enumerate()
+        .filter(|&(index, member)| {
+            members[..index]
+                .iter()
+                .any(|other| other.normalized_email() == member.normalized_email())
+        })
+        .map(|(_, member)| member)
+        .collect()
+}
+
+/// Tries to fix the common malformations of a team list item: an en or em
+/// dash used as the role separator, an email without angle brackets and a
+/// role written before the email. Returns the repaired line, or `None` when
+/// the line can't be turned into a valid team member.
+pub fn repair_team_line(line: &str) -> Option<String> {
+    let mut line = line.trim().replace(['\u{2013}', '\u{2014}'], "-");
+    if !line.starts_with("* ") {
+        line = format!("* {}", line.trim_start_matches(['*', '-']).trim());
+    }
+
+    if !line.contains('<') && !line.contains('>') {
+        let words: Vec<String> = line[2..]
+            .split_whitespace()
+            .map(|word| {
+                if word.contains('@') {
+                    format!("<{}>", word)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect();
+        line = format!("* {}", words.join(" "));
+    }
+
+    // `Name - Role <email>` becomes `Name <email> - Role`, looking for the
+    // dash after the bullet and for the end of the email after its start
+    if let (Some(dash), Some(email_start)) = (line[2..].find(" - "), line.find('<')) {
+        let dash = dash + 2;
+        if dash < email_start {
+            let email_end = email_start + line[email_start..].find('>')?;
+            if !line[email_end + 1..].trim().is_empty() {
+                // text after the email would be lost
+                return None;
+            }
+            let name = line[2..dash].trim();
+            let role = line[dash + 3..email_start].trim();
+            let email = &line[email_start..email_end + 1];
+            line = format!("* {} {} - {}", name, email, role);
+        }
+    }
+
+    Author::parse(&line).map(|author| author.to_string())
 }
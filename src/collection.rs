@@ -0,0 +1,338 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::slice::{Iter, IterMut};
+
+use task::Task;
+
+/// How to resolve tasks with the same ID when merging two collections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Keep our task and drop theirs.
+    KeepMine,
+    /// Replace our task with theirs.
+    KeepTheirs,
+    /// Keep both, giving their task the next free ID.
+    Renumber,
+}
+
+/// The differences between two task collections, as task IDs.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CollectionDiff {
+    /// Tasks only present in the other collection.
+    pub added: Vec<u32>,
+    /// Tasks only present in this collection.
+    pub removed: Vec<u32>,
+    /// Tasks present in both whose type, subject, body or properties differ.
+    pub changed: Vec<u32>,
+}
+
+/// Task counts of a collection.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CollectionStats {
+    pub total: usize,
+    pub by_type: BTreeMap<String, usize>,
+    pub by_status: BTreeMap<String, usize>,
+    pub by_assignee: BTreeMap<String, usize>,
+    /// Tasks without an assignee, not counted in `by_assignee`.
+    pub unassigned: usize,
+    /// Tasks without a status, not counted in `by_status`.
+    pub no_status: usize,
+    /// Tasks without a description.
+    pub empty_body: usize,
+    /// Number of words in all the descriptions.
+    pub body_words: usize,
+    /// Number of characters in all the descriptions.
+    pub body_chars: usize,
+    /// The `Task 5 - subject` line of the task with the most words in its
+    /// description, along with that number of words.
+    pub largest_task: Option<(String, usize)>,
+}
+
+impl CollectionStats {
+    /// Adds the counts of `other`, to get the counts of several files.
+    pub fn add(&mut self, other: &CollectionStats) {
+        fn add_counts(counts: &mut BTreeMap<String, usize>, other: &BTreeMap<String, usize>) {
+            for (name, count) in other {
+                *counts.entry(name.clone()).or_insert(0) += count;
+            }
+        }
+
+        self.total += other.total;
+        add_counts(&mut self.by_type, &other.by_type);
+        add_counts(&mut self.by_status, &other.by_status);
+        add_counts(&mut self.by_assignee, &other.by_assignee);
+        self.unassigned += other.unassigned;
+        self.no_status += other.no_status;
+        self.empty_body += other.empty_body;
+        self.body_words += other.body_words;
+        self.body_chars += other.body_chars;
+        if let Some((ref heading, words)) = other.largest_task {
+            self.add_largest_task(heading, words);
+        }
+    }
+
+    fn add_largest_task(&mut self, heading: &str, words: usize) {
+        let is_larger = self.largest_task.as_ref().is_none_or(|&(_, largest)| words > largest);
+        if is_larger {
+            self.largest_task = Some((heading.to_string(), words));
+        }
+    }
+
+    /// The average number of words of a description, counting the empty ones.
+    pub fn average_body_words(&self) -> f32 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.body_words as f32 / self.total as f32
+        }
+    }
+}
+
+/// The ID following `id`, failing when `id` is the biggest possible one.
+fn next_after(id: u32) -> Result<u32, String> {
+    id.checked_add(1).ok_or_else(|| format!("no task ID is left after {}", id))
+}
+
+/// The tasks of a frump file, in the order they appear in it.
+#[derive(Debug)]
+pub struct TaskCollection {
+    tasks: Vec<Task>,
+}
+
+impl TaskCollection {
+    pub fn new(tasks: Vec<Task>) -> TaskCollection {
+        TaskCollection { tasks }
+    }
+
+    pub fn iter(&self) -> Iter<'_, Task> {
+        self.tasks.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, Task> {
+        self.tasks.iter_mut()
+    }
+
+    pub fn find_by_id(&self, id: u32) -> Option<&Task> {
+        self.tasks.iter().find(|task| task.id == id)
+    }
+
+    pub fn find_by_id_mut(&mut self, id: u32) -> Option<&mut Task> {
+        self.tasks.iter_mut().find(|task| task.id == id)
+    }
+
+    /// Splits the tasks into those matching the predicate and the rest,
+    /// keeping their order.
+    pub fn partition<F: Fn(&Task) -> bool>(&self, f: F) -> (Vec<&Task>, Vec<&Task>) {
+        self.tasks.iter().partition(|task| f(task))
+    }
+
+    /// Counts the tasks in total and by type, status and first assignee, and
+    /// the size of their descriptions.
+    pub fn stats(&self) -> CollectionStats {
+        let mut stats = CollectionStats::default();
+
+        for task in self.iter() {
+            stats.total += 1;
+            *stats.by_type.entry(task.task_type.clone()).or_insert(0) += 1;
+
+            match task.properties().get("Status") {
+                Some(status) => *stats.by_status.entry(status.clone()).or_insert(0) += 1,
+                None => stats.no_status += 1,
+            }
+
+            match task.assignees().first() {
+                Some(author) => *stats.by_assignee.entry(author.name().to_string()).or_insert(0) += 1,
+                None => stats.unassigned += 1,
+            }
+
+            if task.description().is_none() {
+                stats.empty_body += 1;
+            }
+            stats.body_words += task.word_count();
+            stats.body_chars += task.char_count();
+            if task.word_count() > 0 {
+                let heading = format!("{} {} - {}", task.task_type, task.id, task.title);
+                stats.add_largest_task(&heading, task.word_count());
+            }
+        }
+
+        stats
+    }
+
+    /// Returns the tasks whose type is not in the allowed list, which are
+    /// usually typos. An empty list allows any type.
+    pub fn with_unknown_type<S: AsRef<str>>(&self, allowed: &[S]) -> Vec<&Task> {
+        self.tasks.iter().filter(|task| !task.is_known_type(allowed)).collect()
+    }
+
+    /// Returns the tasks whose subject contains the query, ignoring case. More
+    /// than one result means the query is ambiguous. A blank query matches
+    /// nothing.
+    pub fn find_by_subject(&self, query: &str) -> Vec<&Task> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return vec![];
+        }
+
+        self.tasks
+            .iter()
+            .filter(|task| task.title.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    /// The biggest ID in use, or 0 for an empty collection.
+    fn max_id(&self) -> u32 {
+        self.tasks.iter().map(|task| task.id).max().unwrap_or(0)
+    }
+
+    /// The ID following the biggest ID in use, or 1 for an empty collection.
+    /// Fails when the biggest ID in use is the biggest possible one.
+    ///
+    /// Only the current tasks are considered. IDs of closed tasks, which are
+    /// only found in the git history of the file, may be bigger.
+    pub fn next_id(&self) -> Result<u32, String> {
+        next_after(self.max_id())
+    }
+
+    /// The lowest positive ID not in use, for teams preferring dense IDs.
+    ///
+    /// Unlike `next_id` this can return the ID of a task closed before, which
+    /// makes the git history of the two tasks hard to tell apart.
+    pub fn next_id_fill_gaps(&self) -> Result<u32, String> {
+        match self.id_gaps().first() {
+            Some(gap) => Ok(*gap.start()),
+            None => self.next_id(),
+        }
+    }
+
+    /// Adds the tasks of `other` to this collection. Tasks whose ID is not in
+    /// use are added as they are, the rest are resolved by `strategy`.
+    /// Renumbered tasks get IDs above those of both collections, so they never
+    /// take the ID of a task of `other` that is yet to be added. Fails, leaving
+    /// this collection unchanged, when there are no IDs left to renumber.
+    pub fn merge(&mut self, other: &TaskCollection, strategy: MergeStrategy) -> Result<(), String> {
+        let mut tasks = self.tasks.clone();
+        let mut free_id = next_after(self.max_id().max(other.max_id()));
+
+        for task in other.iter() {
+            let existing = tasks.iter().position(|t| t.id == task.id);
+            match (existing, strategy) {
+                (None, _) => tasks.push(task.clone()),
+                (Some(_), MergeStrategy::KeepMine) => {}
+                (Some(index), MergeStrategy::KeepTheirs) => tasks[index] = task.clone(),
+                (Some(_), MergeStrategy::Renumber) => {
+                    let mut task = task.clone();
+                    task.id = free_id.clone()?;
+                    free_id = next_after(task.id);
+                    tasks.push(task);
+                }
+            }
+        }
+
+        self.tasks = tasks;
+        Ok(())
+    }
+
+    /// Returns the IDs used by more than one task, usually left by a merge.
+    pub fn duplicate_ids(&self) -> Vec<u32> {
+        let mut counts = BTreeMap::new();
+        for task in self.iter() {
+            *counts.entry(task.id).or_insert(0) += 1;
+        }
+        counts.into_iter().filter(|&(_, count)| count > 1).map(|(id, _)| id).collect()
+    }
+
+    /// Returns the ranges of unused IDs between 1 and the biggest ID in use.
+    /// These usually belong to closed tasks.
+    pub fn id_gaps(&self) -> Vec<RangeInclusive<u32>> {
+        let mut ids: Vec<u32> = self.tasks.iter().map(|task| task.id).collect();
+        ids.sort();
+        ids.dedup();
+
+        let mut gaps = vec![];
+        let mut expected = 1;
+        for id in ids {
+            if id > expected {
+                gaps.push(expected..=id - 1);
+            }
+            match id.checked_add(1) {
+                Some(next) => expected = next,
+                None => break,
+            }
+        }
+        gaps
+    }
+
+    /// Gives the tasks consecutive IDs starting at `from`, keeping their
+    /// order, and returns the `(old, new)` ID pairs that changed. Fails,
+    /// leaving the tasks unchanged, when `from` is 0 or the last ID would be
+    /// bigger than the biggest possible one.
+    ///
+    /// Task IDs are expected to be unique across the whole git history of the
+    /// file, so renumbering may give a task the ID of an already closed one.
+    pub fn renumber_sequential(&mut self, from: u32) -> Result<Vec<(u32, u32)>, String> {
+        if from == 0 {
+            return Err("task IDs start at 1".to_string());
+        }
+        let count = self.tasks.len() as u64;
+        if count > 0 && u64::from(from) + count - 1 > u64::from(u32::MAX) {
+            return Err(format!("no room for {} task IDs starting at {}", count, from));
+        }
+
+        let mut changes = vec![];
+        for (task, id) in self.tasks.iter_mut().zip(from..=u32::MAX) {
+            if task.id != id {
+                changes.push((task.id, id));
+                task.id = id;
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Compares this collection with `other`, matching tasks by ID.
+    pub fn diff(&self, other: &TaskCollection) -> CollectionDiff {
+        let mut diff = CollectionDiff::default();
+
+        for task in self.iter() {
+            match other.find_by_id(task.id) {
+                None => diff.removed.push(task.id),
+                Some(other_task) => {
+                    if task.differs_from(other_task) {
+                        diff.changed.push(task.id);
+                    }
+                }
+            }
+        }
+
+        diff.added = other
+            .iter()
+            .filter(|task| self.find_by_id(task.id).is_none())
+            .map(|task| task.id)
+            .collect();
+
+        diff
+    }
+
+    /// Changes the type of every task of type `from`, ignoring case, to `to`.
+    /// Returns how many tasks were changed.
+    pub fn retype(&mut self, from: &str, to: &str) -> usize {
+        let mut count = 0;
+        for task in self.tasks.iter_mut().filter(|task| task.task_type.eq_ignore_ascii_case(from)) {
+            task.task_type = to.to_string();
+            count += 1;
+        }
+        count
+    }
+
+    /// Applies `f` to the task with the given ID. Returns `false` when there
+    /// is no such task.
+    pub fn update<F: FnOnce(&mut Task)>(&mut self, id: u32, f: F) -> bool {
+        match self.find_by_id_mut(id) {
+            Some(task) => {
+                f(task);
+                true
+            }
+            None => false,
+        }
+    }
+}
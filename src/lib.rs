@@ -0,0 +1,6 @@
+pub mod task;
+pub mod author;
+pub mod collection;
+pub mod parser;
+pub mod render;
+pub mod search;
@@ -1,58 +1,358 @@
-pub mod task;
-pub mod author;
+extern crate frump;
 
+use std::collections::BTreeMap;
+use std::env;
 use std::io::prelude::*;
-use std::str::*;
-use std::fs::File;
-
-use task::Task;
-
-fn get_task_lines(lines: Lines) -> Vec<Vec<&str>> {
-    let mut result = vec![];
-    let mut task_lines = vec![];
-    let mut is_inside_task_section = false;
-    let mut is_inside_task = false;
-
-    for line in lines {
-        if line.trim().to_uppercase().starts_with("## TASKS") {
-            is_inside_task_section = true;
-        } else if line.trim().starts_with("## ") {
-            is_inside_task_section = false;
-        } else if is_inside_task_section {
-            if line.trim().starts_with("### ") {
-                is_inside_task = true;
-            }
-            if is_inside_task {
-                if line.trim().starts_with("### ") && task_lines.len() > 0 {
-                    result.push(task_lines);
-                    task_lines = vec![];
+use std::fs::{self, File};
+use std::process;
+
+use frump::collection::{CollectionStats, TaskCollection};
+use frump::author::{find_duplicates, repair_team_line};
+use frump::parser::{get_invalid_team_lines, get_malformed_headings, parse, parse_lenient,
+                    parse_team, ParseOptions};
+use frump::render::{render_tasks, ListFormat};
+use frump::task::{is_allowed_type, KNOWN_TYPES};
+
+const USAGE: &str = "usage: frump [team|stats|types|validate|next-id] [--file <path>]... \
+                     [--format oneline|full|table|porcelain] [--porcelain] \
+                     [--type <type>] [--count-only] [--limit <n>] [--offset <n>] [--role <role>] \
+                     [--tasks-heading <text>] [--team-heading <text>] \
+                     [--strict] [--warnings-as-errors] [-q|--quiet] [-v|--verbose]";
+
+enum Command {
+    /// Lists the tasks, the default.
+    List,
+    /// Lists the team members.
+    Team,
+    /// Prints task counts.
+    Stats,
+    /// Lists the task types with their task counts.
+    Types,
+    /// Reports the problems found in the file.
+    Validate,
+    /// Prints the ID to give to a new task.
+    NextId,
+}
+
+/// How much the commands print besides their results. Errors are always
+/// printed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Verbosity {
+    /// Only results and errors, no warnings.
+    Quiet,
+    Normal,
+    /// Also what frump is doing, like the files it reads.
+    Verbose,
+}
+
+struct Args {
+    command: Command,
+    /// The files given with `--file`, which can be repeated, or `frump.md`
+    /// when there are none.
+    file_names: Vec<String>,
+    /// The section headings given with `--tasks-heading` and `--team-heading`.
+    options: ParseOptions,
+    format: ListFormat,
+    /// Make `list` show only the tasks of this type.
+    task_type: Option<String>,
+    /// Make `list` print only the number of tasks it would show.
+    count_only: bool,
+    /// Make `list` show at most this many tasks.
+    limit: Option<usize>,
+    /// Make `list` skip this many tasks.
+    offset: usize,
+    /// Make `team` list only the members with this role.
+    role: Option<String>,
+    /// Make `validate` fail when errors are found.
+    strict: bool,
+    /// Make `validate` fail when errors or warnings are found.
+    warnings_as_errors: bool,
+    verbosity: Verbosity,
+}
+
+impl Args {
+    /// Prints the message to stderr when the verbosity is at least `level`.
+    /// Messages of the `Quiet` level, like errors, are always printed.
+    fn log(&self, level: Verbosity, message: &str) {
+        if self.verbosity >= level {
+            eprintln!("{}", message);
+        }
+    }
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("{}\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn parse_number(arg: &str, value: &str) -> usize {
+    value.parse().unwrap_or_else(|_| {
+        usage_error(&format!("invalid value '{}' for '{}', expected a number", value, arg))
+    })
+}
+
+fn get_args() -> Args {
+    let mut file_names = vec![];
+    let mut options = ParseOptions::default();
+    let mut format = ListFormat::Oneline;
+    let mut task_type = None;
+    let mut count_only = false;
+    let mut limit = None;
+    let mut offset = 0;
+    let mut role = None;
+    let mut strict = false;
+    let mut warnings_as_errors = false;
+    let mut verbosity = Verbosity::Normal;
+    let mut command = None;
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "team" if command.is_none() => command = Some(Command::Team),
+            "stats" if command.is_none() => command = Some(Command::Stats),
+            "types" if command.is_none() => command = Some(Command::Types),
+            "validate" if command.is_none() => command = Some(Command::Validate),
+            "next-id" if command.is_none() => command = Some(Command::NextId),
+            "--porcelain" => format = ListFormat::Porcelain,
+            "--count-only" => count_only = true,
+            "--strict" => strict = true,
+            "--warnings-as-errors" => warnings_as_errors = true,
+            "-q" | "--quiet" => verbosity = Verbosity::Quiet,
+            "-v" | "--verbose" => verbosity = Verbosity::Verbose,
+            "--file" | "--format" | "--type" | "--limit" | "--offset" | "--role" | "--tasks-heading"
+            | "--team-heading" => {
+                let value = args
+                    .next()
+                    .unwrap_or_else(|| usage_error(&format!("missing value for '{}'", arg)));
+                match arg.as_str() {
+                    "--file" => file_names.push(value),
+                    "--type" => task_type = Some(value),
+                    "--limit" => limit = Some(parse_number(&arg, &value)),
+                    "--offset" => offset = parse_number(&arg, &value),
+                    "--role" => role = Some(value),
+                    "--tasks-heading" => options.tasks_heading = value,
+                    "--team-heading" => options.team_heading = value,
+                    _ => format = value.parse().unwrap_or_else(|error: String| usage_error(&error)),
                 }
-                task_lines.push(line);
             }
+            _ => usage_error(&format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    if file_names.is_empty() {
+        file_names.push("frump.md".to_string());
+    }
+    let command = command.unwrap_or(Command::List);
+    if let Command::NextId = command {
+        if file_names.len() > 1 {
+            // IDs are only unique within a file
+            usage_error("next-id takes a single file");
+        }
+    }
+    Args {
+        command,
+        file_names,
+        options,
+        format,
+        task_type,
+        count_only,
+        limit,
+        offset,
+        role,
+        strict,
+        warnings_as_errors,
+        verbosity,
+    }
+}
+
+fn read_file(args: &Args, file_name: &str) -> String {
+    if args.verbosity >= Verbosity::Verbose {
+        match fs::canonicalize(file_name) {
+            Ok(path) => args.log(Verbosity::Verbose, &format!("reading {}", path.display())),
+            Err(_) => args.log(Verbosity::Verbose, &format!("reading {}", file_name)),
+        }
+    }
+
+    let mut file_body = String::new();
+    let result = File::open(file_name).and_then(|mut file| file.read_to_string(&mut file_body));
+    if let Err(error) = result {
+        args.log(Verbosity::Quiet, &format!("{}: {}", file_name, error));
+        process::exit(1);
+    }
+    file_body
+}
+
+fn read_tasks(args: &Args, file_name: &str) -> TaskCollection {
+    match parse(&read_file(args, file_name), &args.options) {
+        Ok(tasks) => tasks,
+        Err(error) => {
+            args.log(Verbosity::Quiet, &format!("{}: {}", file_name, error));
+            process::exit(1);
         }
     }
+}
+
+/// Prints the counts under the title, unless there is nothing counted, like
+/// the statuses of tasks without any.
+fn print_counts(title: &str, counts: &BTreeMap<String, usize>, missing: usize) {
+    if counts.is_empty() {
+        return;
+    }
 
-    // add last task
-    if task_lines.len() > 0 {
-        result.push(task_lines);
+    println!("{}:", title);
+    for (name, count) in counts {
+        println!("    {}: {}", name, count);
     }
+    if missing > 0 {
+        println!("    (none): {}", missing);
+    }
+}
 
-    result
+fn print_stats(stats: &CollectionStats) {
+    println!("Tasks: {}", stats.total);
+    print_counts("By type", &stats.by_type, 0);
+    print_counts("By status", &stats.by_status, stats.no_status);
+    print_counts("By assignee", &stats.by_assignee, stats.unassigned);
+    println!("Tasks with empty body: {}", stats.empty_body);
+    println!("Body words: {}", stats.body_words);
+    println!("Body characters: {}", stats.body_chars);
+    println!("Average body words: {:.1}", stats.average_body_words());
+    if let Some((ref heading, words)) = stats.largest_task {
+        println!("Largest task (by words): {} ({} words)", heading, words);
+    }
+}
+
+/// Prints the known task types followed by the custom types in use, each
+/// with its number of tasks. Types are compared ignoring case, see
+/// `is_allowed_type`, so `bug` tasks are counted as `Bug` ones.
+fn print_types(stats: &CollectionStats) {
+    for known_type in KNOWN_TYPES {
+        let count: usize = stats.by_type
+            .iter()
+            .filter(|&(task_type, _)| is_allowed_type(task_type, &[known_type]))
+            .map(|(_, count)| count)
+            .sum();
+        println!("{}: {}", known_type, count);
+    }
+    for (task_type, count) in &stats.by_type {
+        if !is_allowed_type(task_type, KNOWN_TYPES) {
+            println!("{}: {} (custom)", task_type, count);
+        }
+    }
+}
+
+/// Prints the problems found in the file and returns the number of errors
+/// and warnings. Warnings are not printed in quiet mode.
+fn validate(args: &Args, file_name: &str) -> (usize, usize) {
+    let content = read_file(args, file_name);
+    let options = &args.options;
+    let (tasks, parse_errors) = parse_lenient(&content, options);
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    let mut error = |message: String| {
+        args.log(Verbosity::Quiet, &format!("{}: error: {}", file_name, message));
+        errors += 1;
+    };
+    for parse_error in parse_errors {
+        error(parse_error.to_string());
+    }
+    for id in tasks.duplicate_ids() {
+        error(format!("task ID {} is used more than once", id));
+    }
+
+    let mut warn = |message: String| {
+        args.log(Verbosity::Normal, &format!("{}: warning: {}", file_name, message));
+        warnings += 1;
+    };
+    let mut types: Vec<&str> = KNOWN_TYPES.to_vec();
+    types.extend(tasks.iter().map(|task| task.task_type.as_str()));
+    for (line, text) in get_malformed_headings(content.lines(), options, &types) {
+        warn(format!("line {}: looks like a task heading without a valid ID, kept in the body: {}",
+                     line, text.trim()));
+    }
+    for (line, text) in get_invalid_team_lines(content.lines(), options) {
+        let mut message = format!("line {}: not a valid team member: {}", line, text.trim());
+        if let Some(repaired) = repair_team_line(text) {
+            message.push_str(&format!(", did you mean '{}'?", repaired));
+        }
+        warn(message);
+    }
+    for member in find_duplicates(&parse_team(&content, options)) {
+        warn(format!("team member email {} is listed more than once", member.email()));
+    }
+
+    (errors, warnings)
 }
 
 fn main() {
-    let file_name = "frump.md".to_string();
+    let args = get_args();
+    let mut errors = 0;
+    let mut warnings = 0;
+    // the counts of all the files together
+    let mut stats = CollectionStats::default();
+    // the tasks matching the filters in all the files, and those shown
+    let mut listed = 0;
+    let mut shown = 0;
 
-    let mut file = File::open(file_name).unwrap();
-    let mut file_body = String::new();
-    file.read_to_string(&mut file_body).unwrap();
+    for file_name in &args.file_names {
+        match args.command {
+            Command::List => {
+                let tasks = read_tasks(&args, file_name);
+                let (tasks, _) = tasks.partition(|task| match args.task_type {
+                    Some(ref task_type) => task_type.eq_ignore_ascii_case(&task.task_type),
+                    None => true,
+                });
 
-    let tasks = get_task_lines(file_body.lines())
-        .into_iter()
-        .map(|task_lines| Task::new(&task_lines))
-        .collect::<Vec<_>>();
+                // the limit and offset apply to the tasks of all the files
+                let skip = args.offset.saturating_sub(listed).min(tasks.len());
+                let take = args.limit.map_or(tasks.len(), |limit| limit - shown);
+                let page: Vec<_> = tasks.iter().skip(skip).take(take).cloned().collect();
+                listed += tasks.len();
+                shown += page.len();
+
+                if !args.count_only {
+                    // IDs are only unique within a file, so qualify them by source
+                    let source =
+                        if args.file_names.len() > 1 { Some(file_name.as_str()) } else { None };
+                    print!("{}", render_tasks(&page, source, args.format));
+                }
+            }
+            Command::Stats | Command::Types => stats.add(&read_tasks(&args, file_name).stats()),
+            Command::Validate => {
+                let (file_errors, file_warnings) = validate(&args, file_name);
+                errors += file_errors;
+                warnings += file_warnings;
+            }
+            Command::NextId => match read_tasks(&args, file_name).next_id() {
+                Ok(id) => println!("{}", id),
+                Err(error) => {
+                    args.log(Verbosity::Quiet, &format!("{}: {}", file_name, error));
+                    process::exit(1);
+                }
+            },
+            Command::Team => {
+                let members = parse_team(&read_file(&args, file_name), &args.options);
+                for member in &members {
+                    if args.role.as_ref().is_none_or(|role| member.has_role(role)) {
+                        println!("{}", member);
+                    }
+                }
+            }
+        }
+    }
+
+    match args.command {
+        Command::List if args.count_only => println!("{}", shown),
+        Command::List if shown < listed => {
+            args.log(Verbosity::Normal, &format!("showing {} of {}", shown, listed));
+        }
+        Command::Stats => print_stats(&stats),
+        Command::Types => print_types(&stats),
+        _ => {}
+    }
 
-    for task in tasks {
-        println!("{} {} - {}", task.task_type, task.id, task.title);
+    if (args.strict && errors > 0) || (args.warnings_as_errors && errors + warnings > 0) {
+        process::exit(1);
     }
 }
@@ -0,0 +1,517 @@
+use std::fmt;
+use std::io::{self, BufRead};
+use std::str::*;
+
+use author::Author;
+use collection::TaskCollection;
+use task::{is_heading, looks_like_heading, Task};
+
+/// Options controlling how a frump file is parsed.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Text of the size 2 heading starting the _Tasks_ section.
+    pub tasks_heading: String,
+    /// Text of the size 2 heading starting the _Team_ section.
+    pub team_heading: String,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            tasks_heading: "Tasks".to_string(),
+            team_heading: "Team".to_string(),
+        }
+    }
+}
+
+/// The kind of a parse error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A task heading or its content is not valid.
+    InvalidTask,
+    /// The file contains unresolved git merge conflict markers.
+    MergeConflict,
+    /// A fenced code block or the task markers of a task body are never
+    /// closed.
+    UnclosedBlock,
+    /// The file could not be read.
+    Io,
+}
+
+/// An error found while parsing a frump file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Number of the line where the error was found, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Whether the line is a size 2 heading with the given text, ignoring case.
+/// The whole text must match, so `## Background` is not a `Back` heading.
+fn is_section_heading(line: &str, heading: &str) -> bool {
+    match line.trim().strip_prefix("## ") {
+        Some(text) => text.trim().to_uppercase() == heading.trim().to_uppercase(),
+        None => false,
+    }
+}
+
+/// Removes a UTF-8 byte order mark and a stray carriage return from a line,
+/// as left by some Windows editors.
+fn clean_line(line: &str) -> &str {
+    line.trim_start_matches('\u{feff}').trim_end_matches('\r')
+}
+
+/// Optional marker lines enclosing a task body, so the body can contain any
+/// markdown, even `## ` headings, without ending the task.
+pub const TASK_START_MARKER: &str = "<!-- frump:task -->";
+pub const TASK_END_MARKER: &str = "<!-- /frump:task -->";
+
+/// Where a line of a frump file belongs, see `TaskSplitter`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    /// The line is outside of the _Tasks_ section.
+    Outside,
+    /// The line belongs to the current group of task lines.
+    Task,
+    /// The line is a task heading, starting a new group of task lines.
+    Heading,
+}
+
+/// Tells which lines of a frump file belong to tasks, fed one line at a time.
+struct TaskSplitter {
+    tasks_heading: String,
+    /// Number of the last classified line, starting from 1.
+    line_number: usize,
+    /// Number of the line opening the current code block or task markers.
+    block_start: usize,
+    is_inside_task_section: bool,
+    is_inside_task: bool,
+    is_inside_code_block: bool,
+    is_inside_task_markers: bool,
+}
+
+impl TaskSplitter {
+    fn new(options: &ParseOptions) -> TaskSplitter {
+        TaskSplitter {
+            tasks_heading: options.tasks_heading.clone(),
+            line_number: 0,
+            block_start: 0,
+            is_inside_task_section: false,
+            is_inside_task: false,
+            is_inside_code_block: false,
+            is_inside_task_markers: false,
+        }
+    }
+
+    fn classify(&mut self, line: &str) -> LineKind {
+        self.line_number += 1;
+
+        // everything between the task markers is body, whatever it looks like
+        if self.is_inside_task_markers {
+            self.is_inside_task_markers = line.trim() != TASK_END_MARKER;
+            return LineKind::Task;
+        }
+
+        let is_inside_body = self.is_inside_task_section && self.is_inside_task;
+        if is_inside_body && !self.is_inside_code_block && line.trim() == TASK_START_MARKER {
+            self.is_inside_task_markers = true;
+            self.block_start = self.line_number;
+            return LineKind::Task;
+        }
+
+        let is_fence = is_inside_body && line.trim_start().starts_with("```");
+        if is_fence {
+            self.is_inside_code_block = !self.is_inside_code_block;
+            self.block_start = self.line_number;
+        }
+
+        // an indented line of a task body is either an indented code block or
+        // the continuation of a list item
+        let is_indented = is_inside_body && (line.starts_with("    ") || line.starts_with('\t'));
+
+        if is_fence || is_indented || self.is_inside_code_block {
+            // code in a task body is never a heading
+            LineKind::Task
+        } else if is_section_heading(line, &self.tasks_heading) {
+            self.is_inside_task_section = true;
+            LineKind::Outside
+        } else if line.trim().starts_with("## ") {
+            self.is_inside_task_section = false;
+            LineKind::Outside
+        } else if !self.is_inside_task_section {
+            LineKind::Outside
+        } else if is_heading(line) {
+            self.is_inside_task = true;
+            LineKind::Heading
+        } else {
+            LineKind::Task
+        }
+    }
+
+    /// Whether the last classified line left a code block or task markers
+    /// open, so the next line is part of a task body whatever it looks like.
+    fn is_inside_block(&self) -> bool {
+        self.is_inside_code_block || self.is_inside_task_markers
+    }
+
+    /// Returns an error when the lines classified so far end inside a code
+    /// block or the task markers, which then took every line after their
+    /// opening line.
+    fn finish(&self) -> Option<ParseError> {
+        let message =
+            if self.is_inside_task_markers {
+                format!("the task markers are never closed with '{}'", TASK_END_MARKER)
+            } else if self.is_inside_code_block {
+                "the code block is never closed".to_string()
+            } else {
+                return None;
+            };
+
+        Some(ParseError {
+            kind: ParseErrorKind::UnclosedBlock,
+            line: self.block_start,
+            message,
+        })
+    }
+}
+
+/// Lines along with their line numbers, starting from 1.
+type NumberedLines<'a> = Vec<(usize, &'a str)>;
+
+/// Splits the _Tasks_ section into groups of numbered lines, one group per
+/// task, see `get_task_lines`. Also returns the error of an unclosed code
+/// block or task markers, if any.
+fn split_tasks<'a>(lines: Lines<'a>, options: &ParseOptions)
+                   -> (Vec<NumberedLines<'a>>, Option<ParseError>) {
+    let mut result = vec![];
+    let mut task_lines = vec![];
+    let mut is_inside_task = false;
+    let mut splitter = TaskSplitter::new(options);
+
+    for (index, line) in lines.map(clean_line).enumerate() {
+        match splitter.classify(line) {
+            LineKind::Outside => {}
+            LineKind::Task => task_lines.push((index + 1, line)),
+            LineKind::Heading => {
+                if is_inside_task {
+                    result.push(task_lines);
+                    task_lines = vec![];
+                }
+                is_inside_task = true;
+                task_lines.push((index + 1, line));
+            }
+        }
+    }
+
+    // add last task
+    if is_inside_task {
+        result.push(task_lines);
+    }
+
+    (result, splitter.finish())
+}
+
+/// Splits the _Tasks_ section into groups of lines, one group per task.
+///
+/// Every group contains the task heading and all the lines up to the next
+/// task heading. Other `### ` headings (see `task::is_heading`) are part of
+/// the body, and so is everything inside code blocks or between the
+/// `TASK_START_MARKER` and `TASK_END_MARKER` lines of a task. Any text placed
+/// in the _Tasks_ section before the first task heading is kept at the
+/// beginning of the first group, so it is not lost.
+pub fn get_task_lines<'a>(lines: Lines<'a>, options: &ParseOptions) -> Vec<Vec<&'a str>> {
+    split_tasks(lines, options)
+        .0
+        .into_iter()
+        .map(|task_lines| task_lines.into_iter().map(|(_, line)| line).collect())
+        .collect()
+}
+
+/// Parses a group of numbered task lines, as made by `split_tasks`.
+fn parse_task_lines(task_lines: &[(usize, &str)]) -> Result<Task, ParseError> {
+    let heading_line = task_lines
+        .iter()
+        .find(|&&(_, line)| is_heading(line))
+        .map_or(0, |&(number, _)| number);
+    let lines: Vec<&str> = task_lines.iter().map(|&(_, line)| line).collect();
+
+    Task::parse(&lines).map_err(|message| ParseError {
+        kind: ParseErrorKind::InvalidTask,
+        line: heading_line,
+        message,
+    })
+}
+
+/// Whether the line starts a git merge conflict.
+fn is_merge_conflict(line: &str) -> bool {
+    line.starts_with("<<<<<<<")
+}
+
+fn merge_conflict_error(line: usize) -> ParseError {
+    ParseError {
+        kind: ParseErrorKind::MergeConflict,
+        line,
+        message: "unresolved merge conflict".to_string(),
+    }
+}
+
+/// Returns an error for every git merge conflict left in the content, found
+/// by its `<<<<<<<` marker line. Such lines inside a code block or the task
+/// markers of a task body, like in a task documenting how to resolve a
+/// conflict, are not conflicts.
+fn find_merge_conflicts(content: &str, options: &ParseOptions) -> Vec<ParseError> {
+    let mut errors = vec![];
+    let mut splitter = TaskSplitter::new(options);
+
+    for (index, line) in content.lines().map(clean_line).enumerate() {
+        if is_merge_conflict(line) && !splitter.is_inside_block() {
+            errors.push(merge_conflict_error(index + 1));
+        }
+        splitter.classify(line);
+    }
+
+    errors
+}
+
+/// Parses every task of the _Tasks_ section, returning the tasks that could
+/// be parsed and an error for each one that could not. A file with merge
+/// conflicts can't be trusted, so then only the conflicts are returned.
+fn parse_all(content: &str, options: &ParseOptions) -> (Vec<Task>, Vec<ParseError>) {
+    let mut tasks = vec![];
+    let mut errors = find_merge_conflicts(content, options);
+    if !errors.is_empty() {
+        return (tasks, errors);
+    }
+
+    let (groups, block_error) = split_tasks(content.lines(), options);
+    for task_lines in groups {
+        match parse_task_lines(&task_lines) {
+            Ok(task) => tasks.push(task),
+            Err(error) => errors.push(error),
+        }
+    }
+    errors.extend(block_error);
+
+    (tasks, errors)
+}
+
+/// Parses a standalone task block, starting with its `### ` heading, such as a
+/// single task cut out of a frump file. Fails when the block contains more
+/// than one task heading, as it would otherwise merge several tasks into one.
+pub fn parse_single_task(content: &str) -> Result<Task, ParseError> {
+    let lines: Vec<&str> = content.lines().map(clean_line).collect();
+
+    // the splitter only tracks the code blocks and task markers here, a
+    // block has no sections so a `## ` line doesn't end it
+    let mut splitter = TaskSplitter::new(&ParseOptions::default());
+    let mut heading_lines = vec![];
+    for (index, line) in lines.iter().enumerate() {
+        splitter.is_inside_task_section = true;
+        let is_inside_block = splitter.is_inside_block();
+        let is_indented = line.starts_with("    ") || line.starts_with('\t');
+        splitter.classify(line);
+        if !is_inside_block && !is_indented && is_heading(line) {
+            heading_lines.push(index + 1);
+        }
+    }
+
+    let error = |line, message| ParseError { kind: ParseErrorKind::InvalidTask, line, message };
+    if heading_lines.len() > 1 {
+        let message = "expected a single task, found another task heading".to_string();
+        return Err(error(heading_lines[1], message));
+    }
+
+    let heading_line = heading_lines.first().cloned().unwrap_or(1);
+    Task::parse(&lines).map_err(|message| error(heading_line, message))
+}
+
+/// Parses the tasks of a frump file, failing on the first malformed task.
+pub fn parse(content: &str, options: &ParseOptions) -> Result<TaskCollection, ParseError> {
+    let (tasks, mut errors) = parse_all(content, options);
+    if errors.is_empty() {
+        Ok(TaskCollection::new(tasks))
+    } else {
+        Err(errors.remove(0))
+    }
+}
+
+/// Parses the tasks of a frump file, skipping the malformed tasks instead of
+/// failing. Returns the tasks that could be parsed together with an error
+/// for every skipped one.
+pub fn parse_lenient(content: &str, options: &ParseOptions) -> (TaskCollection, Vec<ParseError>) {
+    let (tasks, errors) = parse_all(content, options);
+    (TaskCollection::new(tasks), errors)
+}
+
+/// Returns the lines of the _Tasks_ section that look like task headings with
+/// a bad or missing ID, see `task::looks_like_heading`, along with their line
+/// numbers. Such lines are kept in the body of the task before them, so they
+/// are worth a warning. Lines inside code blocks or task markers are skipped.
+pub fn get_malformed_headings<'a>(lines: Lines<'a>, options: &ParseOptions, types: &[&str])
+                                  -> Vec<(usize, &'a str)> {
+    let mut result = vec![];
+    let mut splitter = TaskSplitter::new(options);
+
+    for (index, line) in lines.map(clean_line).enumerate() {
+        let is_inside_block = splitter.is_inside_block();
+        let is_indented = line.starts_with("    ") || line.starts_with('\t');
+        if splitter.classify(line) == LineKind::Task
+            && !is_inside_block
+            && !is_indented
+            && looks_like_heading(line, types) {
+            result.push((index + 1, line));
+        }
+    }
+
+    result
+}
+
+/// Returns the non-blank lines of the _Team_ section along with their line
+/// numbers, starting from 1. A `## Team` line inside a task body, like in its
+/// code blocks or task markers, does not start the section.
+pub fn get_team_lines<'a>(lines: Lines<'a>, options: &ParseOptions) -> Vec<(usize, &'a str)> {
+    let mut result = vec![];
+    let mut is_inside_team_section = false;
+    let mut splitter = TaskSplitter::new(options);
+
+    for (index, line) in lines.map(clean_line).enumerate() {
+        if splitter.classify(line) != LineKind::Outside {
+            continue;
+        }
+
+        if is_section_heading(line, &options.team_heading) {
+            is_inside_team_section = true;
+        } else if line.trim().starts_with("## ") {
+            is_inside_team_section = false;
+        } else if is_inside_team_section && !line.trim().is_empty() {
+            result.push((index + 1, line));
+        }
+    }
+
+    result
+}
+
+/// Returns the lines of the _Team_ section that are not valid team members
+/// and would otherwise be silently ignored, along with their line numbers.
+pub fn get_invalid_team_lines<'a>(lines: Lines<'a>, options: &ParseOptions) -> Vec<(usize, &'a str)> {
+    get_team_lines(lines, options)
+        .into_iter()
+        .filter(|&(_, line)| Author::parse(line).is_none())
+        .collect()
+}
+
+/// Parses the members listed in the _Team_ section, in their order. Lines
+/// that are not valid team members are skipped, see `get_invalid_team_lines`.
+pub fn parse_team(content: &str, options: &ParseOptions) -> Vec<Author> {
+    get_team_lines(content.lines(), options)
+        .into_iter()
+        .filter_map(|(_, line)| Author::parse(line))
+        .collect()
+}
+
+/// Parses tasks one at a time while reading, see `parse_tasks`.
+pub struct TaskReader<R> {
+    lines: io::Lines<R>,
+    splitter: TaskSplitter,
+    line_number: usize,
+    /// Lines of the task being read, with the line numbers.
+    task_lines: Vec<(usize, String)>,
+    is_inside_task: bool,
+    is_done: bool,
+    /// An error to yield after the last task.
+    final_error: Option<ParseError>,
+}
+
+impl<R: BufRead> TaskReader<R> {
+    /// Returns the task read so far, if any, starting a new one.
+    fn take_task(&mut self) -> Option<Result<Task, ParseError>> {
+        if !self.is_inside_task {
+            return None;
+        }
+
+        let task_lines: Vec<(usize, &str)> = self.task_lines
+            .iter()
+            .map(|&(number, ref line)| (number, line.as_str()))
+            .collect();
+        let task = parse_task_lines(&task_lines);
+        self.task_lines.clear();
+        Some(task)
+    }
+}
+
+impl<R: BufRead> Iterator for TaskReader<R> {
+    type Item = Result<Task, ParseError>;
+
+    fn next(&mut self) -> Option<Result<Task, ParseError>> {
+        while !self.is_done {
+            let line = match self.lines.next() {
+                Some(Ok(line)) => line,
+                Some(Err(error)) => {
+                    self.is_done = true;
+                    return Some(Err(ParseError {
+                        kind: ParseErrorKind::Io,
+                        line: self.line_number + 1,
+                        message: error.to_string(),
+                    }));
+                }
+                None => {
+                    // add last task
+                    self.is_done = true;
+                    self.final_error = self.splitter.finish();
+                    match self.take_task() {
+                        Some(task) => return Some(task),
+                        None => break,
+                    }
+                }
+            };
+            self.line_number += 1;
+
+            let line = clean_line(&line).to_string();
+            if is_merge_conflict(&line) && !self.splitter.is_inside_block() {
+                // nothing after a merge conflict can be trusted
+                self.is_done = true;
+                return Some(Err(merge_conflict_error(self.line_number)));
+            }
+
+            match self.splitter.classify(&line) {
+                LineKind::Outside => {}
+                LineKind::Task => self.task_lines.push((self.line_number, line)),
+                LineKind::Heading => {
+                    let task = self.take_task();
+                    self.is_inside_task = true;
+                    self.task_lines.push((self.line_number, line));
+                    if task.is_some() {
+                        return task;
+                    }
+                }
+            }
+        }
+
+        self.final_error.take().map(Err)
+    }
+}
+
+/// Parses the tasks of a frump file while reading it, yielding each task as
+/// soon as it is complete instead of holding the whole file in memory. Use
+/// it to scan very large files; `parse` is simpler otherwise. Reading stops
+/// at the first merge conflict or read error, which is yielded as an error.
+/// Unlike with `parse_lenient`, the tasks before a merge conflict have then
+/// already been yielded.
+pub fn parse_tasks<R: BufRead>(reader: R, options: &ParseOptions) -> TaskReader<R> {
+    TaskReader {
+        lines: reader.lines(),
+        splitter: TaskSplitter::new(options),
+        line_number: 0,
+        task_lines: vec![],
+        is_inside_task: false,
+        is_done: false,
+        final_error: None,
+    }
+}
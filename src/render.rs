@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+use task::Task;
+
+/// Maximum number of characters of the body shown by the `Full` format.
+const SNIPPET_LENGTH: usize = 72;
+
+/// How a list of tasks is printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListFormat {
+    /// One `[5] Task: subject @assignee #status` line per task, without the
+    /// assignee and status when the task has none.
+    Oneline,
+    /// A `Task 5 - subject` line followed by the start of the task's body.
+    Full,
+    /// Columns with the ID, type and subject, aligned to their content.
+    Table,
+    /// Stable tab separated `id, type, status, assignee, subject` lines for
+    /// scripts, without any header or decoration. Tabs and line breaks in the
+    /// fields are replaced with spaces.
+    Porcelain,
+}
+
+impl FromStr for ListFormat {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<ListFormat, String> {
+        match text {
+            "oneline" => Ok(ListFormat::Oneline),
+            "full" => Ok(ListFormat::Full),
+            "table" => Ok(ListFormat::Table),
+            "porcelain" => Ok(ListFormat::Porcelain),
+            _ => Err(format!("unknown format '{}', expected oneline, full, table or porcelain", text)),
+        }
+    }
+}
+
+/// The first line of the body, cut to `SNIPPET_LENGTH` characters.
+fn snippet(task: &Task) -> Option<String> {
+    let line = task.description()?.lines().next()?.trim();
+    if line.chars().count() > SNIPPET_LENGTH {
+        Some(format!("{}...", line.chars().take(SNIPPET_LENGTH).collect::<String>()))
+    } else {
+        Some(line.to_string())
+    }
+}
+
+/// Replaces the characters separating the fields and lines of the `Porcelain`
+/// format with spaces.
+fn porcelain_field(text: &str) -> String {
+    text.replace(['\t', '\n', '\r'], " ")
+}
+
+/// Renders the tasks in the given format, one or more lines per task. When
+/// `source` is given, IDs are qualified with it like `frump.md:5`, since IDs
+/// are only unique within a file.
+pub fn render_tasks(tasks: &[&Task], source: Option<&str>, format: ListFormat) -> String {
+    let ids: Vec<String> = tasks
+        .iter()
+        .map(|task| match source {
+            Some(source) => format!("{}:{}", source, task.id),
+            None => task.id.to_string(),
+        })
+        .collect();
+
+    let mut output = String::new();
+    match format {
+        ListFormat::Porcelain => {
+            for (task, id) in tasks.iter().zip(&ids) {
+                let status = task.properties().get("Status").map_or("", |status| status.as_str());
+                let assignee = task.assignees().first().map_or("", |author| author.name());
+                let fields = [id.as_str(), &task.task_type, status, assignee, &task.title];
+                let fields: Vec<String> = fields.iter().map(|field| porcelain_field(field)).collect();
+                output.push_str(&fields.join("\t"));
+                output.push('\n');
+            }
+        }
+        ListFormat::Oneline => {
+            for (task, id) in tasks.iter().zip(&ids) {
+                output.push_str(&format!("[{}] {}: {}", id, task.task_type, task.title));
+                if let Some(author) = task.assignees().first() {
+                    output.push_str(&format!(" @{}", author.name()));
+                }
+                if let Some(status) = task.properties().get("Status") {
+                    output.push_str(&format!(" #{}", status));
+                }
+                output.push('\n');
+            }
+        }
+        ListFormat::Full => {
+            for (task, id) in tasks.iter().zip(&ids) {
+                output.push_str(&format!("{} {} - {}\n", task.task_type, id, task.title));
+                if let Some(snippet) = snippet(task) {
+                    output.push_str(&format!("    {}\n", snippet));
+                }
+            }
+        }
+        ListFormat::Table => {
+            let id_width = ids.iter().map(|id| id.chars().count()).fold(2, usize::max);
+            let type_width = tasks
+                .iter()
+                .map(|task| task.task_type.chars().count())
+                .fold(4, usize::max);
+
+            output.push_str(&format!("{:<iw$}  {:<tw$}  Subject\n", "ID", "Type",
+                                     iw = id_width, tw = type_width));
+            for (task, id) in tasks.iter().zip(&ids) {
+                output.push_str(&format!("{:<iw$}  {:<tw$}  {}\n", id, task.task_type, task.title,
+                                         iw = id_width, tw = type_width));
+            }
+        }
+    }
+    output
+}
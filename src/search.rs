@@ -0,0 +1,89 @@
+use collection::TaskCollection;
+
+/// Scores below this value are not considered a match.
+const MIN_SCORE: f32 = 0.6;
+
+/// Number of single character edits needed to turn `a` into `b`.
+fn levenshtein(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..b.len() + 1).collect();
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// Similarity of two words, from 0 (nothing in common) to 1 (equal).
+fn word_similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    1.0 - levenshtein(&a, &b) as f32 / longest as f32
+}
+
+/// Scores how well the text matches the query, from 0 to 1. An exact
+/// substring match scores 1, otherwise every query word is compared with the
+/// most similar word of the text, which tolerates typos. A blank query
+/// matches nothing.
+pub fn score(query: &str, text: &str) -> f32 {
+    let query = query.trim().to_lowercase();
+    let text = text.to_lowercase();
+    if query.is_empty() {
+        return 0.0;
+    }
+    if text.contains(&query) {
+        return 1.0;
+    }
+
+    let text_words: Vec<&str> = text.split_whitespace().collect();
+    let query_words: Vec<&str> = query.split_whitespace().collect();
+    if query_words.is_empty() || text_words.is_empty() {
+        return 0.0;
+    }
+
+    let total: f32 = query_words
+        .iter()
+        .map(|query_word| {
+            text_words
+                .iter()
+                .map(|text_word| word_similarity(query_word, text_word))
+                .fold(0.0, f32::max)
+        })
+        .sum();
+
+    total / query_words.len() as f32
+}
+
+/// Ranks the tasks matching the query, best match first, returning their IDs
+/// with the match score. The subject is always searched, the description
+/// only when `full` is set.
+pub fn rank(query: &str, tasks: &TaskCollection, full: bool) -> Vec<(u32, f32)> {
+    let mut result: Vec<(u32, f32)> = tasks
+        .iter()
+        .map(|task| {
+            let mut best = score(query, &task.title);
+            if full {
+                if let Some(description) = task.description() {
+                    best = best.max(score(query, description));
+                }
+            }
+            (task.id, best)
+        })
+        .filter(|&(_, score)| score >= MIN_SCORE)
+        .collect();
+
+    result.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap().then(a.0.cmp(&b.0)));
+    result
+}
@@ -3,42 +3,237 @@ use std::str::FromStr;
 
 use author::Author;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Task {
     pub task_type: String,
     pub id: u32,
     pub title: String,
+    leading_notes: Option<String>,
     description: Option<String>,
     assignees: Vec<Author>,
     properties: HashMap<String, String>,
 }
 
+/// The task types suggested by the README. Any other type is a custom one.
+pub const KNOWN_TYPES: &[&str] = &["Task", "Bug", "Issue"];
+
+/// A part of a task that can differ between two versions of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskField {
+    Id,
+    Type,
+    Subject,
+    Body,
+    Properties,
+}
+
+/// Whether the task type is one of the allowed types, like `KNOWN_TYPES`,
+/// ignoring case. An empty list allows any type.
+pub fn is_allowed_type<S: AsRef<str>>(task_type: &str, allowed: &[S]) -> bool {
+    allowed.is_empty() || allowed.iter().any(|t| t.as_ref().eq_ignore_ascii_case(task_type))
+}
+
+/// Parses a task ID typed by a user, like `5`, `#5` or, when a prefix is
+/// given, `T-5` for the prefix `T-`. Only digits may follow the prefix or
+/// the `#`, so `+5` and `T-#5` are rejected.
+pub fn parse_id(text: &str, prefix: Option<&str>) -> Result<u32, String> {
+    let text = text.trim();
+    let number = match prefix.and_then(|prefix| text.strip_prefix(prefix)) {
+        Some(number) => number,
+        None => text.strip_prefix('#').unwrap_or(text),
+    };
+
+    let is_number = !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+    match number.parse::<u32>() {
+        Ok(id) if is_number && id > 0 => Ok(id),
+        _ => Err(format!("'{}' is not a valid task ID, expected a positive number like 5 or #5", text)),
+    }
+}
+
+/// Two tasks are equal when their ID, type, subject, body and properties are
+/// equal. Leading notes are not part of the task itself and are ignored.
+impl PartialEq for Task {
+    fn eq(&self, other: &Task) -> bool {
+        !self.differs_from(other)
+    }
+}
+
+impl Eq for Task {}
+
+/// Whether the line is a task heading, that is a `### ` heading whose second
+/// word is the task ID, all digits with an optional `#` before them. Other
+/// `### ` headings, like `### Details` or `### Step 1: download`, are part of
+/// a task body; see `looks_like_heading` for the malformed task headings
+/// among them.
+pub fn is_heading(line: &str) -> bool {
+    let line = line.trim();
+    if !line.starts_with("### ") {
+        return false;
+    }
+
+    match line[4..].split_whitespace().nth(1) {
+        Some(id) => {
+            let digits = id.strip_prefix('#').unwrap_or(id);
+            !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// Whether a `### ` line which is not a task heading looks like a task
+/// heading with a bad or missing ID, like `### Task - subject` or
+/// `### Bug5 - subject`: its first word starts with one of the given task
+/// types, ignoring case, and it has a ` - ` separator or a second word
+/// starting with a digit.
+pub fn looks_like_heading(line: &str, types: &[&str]) -> bool {
+    let line = line.trim();
+    if !line.starts_with("### ") || is_heading(line) {
+        return false;
+    }
+
+    let words: Vec<&str> = line[4..].split_whitespace().collect();
+    let first = words.first().map_or(String::new(), |word| word.to_lowercase());
+    let is_type = types.iter().any(|task_type| first.starts_with(&task_type.to_lowercase()));
+    let has_id = words.get(1).is_some_and(|word| word.starts_with(|c: char| c.is_ascii_digit()));
+    let has_id_or_separator = has_id || words.contains(&"-");
+    is_type && has_id_or_separator
+}
+
+/// Splits the first whitespace separated word off the text, returning it
+/// along with the rest of the text.
+fn split_word(text: &str) -> (&str, &str) {
+    let text = text.trim_start();
+    match text.find(char::is_whitespace) {
+        Some(index) => (&text[..index], text[index..].trim_start()),
+        None => (text, ""),
+    }
+}
+
+/// Joins the lines into a single text, dropping the blank lines around it.
+fn join_text(lines: &[&str]) -> Option<String> {
+    let start = lines.iter().position(|line| !line.trim().is_empty());
+    let end = lines.iter().rposition(|line| !line.trim().is_empty());
+
+    match (start, end) {
+        (Some(start), Some(end)) => Some(lines[start..end + 1].join("\n")),
+        _ => None,
+    }
+}
+
 impl Task {
-    pub fn new(lines: &Vec<&str>) -> Task {
-        let subject = lines[0][4..].to_string();
-
-        let words: Vec<&str> = lines[0][4..].split_whitespace().collect();
-
-        let task_type = words[0].to_string();
-        let id_str = words[1];
-        let id: u32 = FromStr::from_str(id_str).unwrap();
-
-        let title =
-            if words[2] == "-" {
-                let index = subject.find('-').unwrap();
-                subject[index + 1..].trim().to_string()
-            } else {
-                let index = subject.find(id_str).unwrap();
-                subject[index + id_str.len() + 1..].trim().to_string()
-            };
-
-        Task {
-            id: id,
-            title: title,
-            task_type: task_type,
-            description: None,
+    /// Parses a task from its lines. The lines before the task heading, if
+    /// any, are kept as the task's leading notes, and the lines after it as
+    /// the task's description. Fails when the heading is missing or is not
+    /// like `### <Type> <ID> - <subject>`.
+    pub fn parse(lines: &[&str]) -> Result<Task, String> {
+        let heading_index = lines
+            .iter()
+            .position(|line| is_heading(line))
+            .ok_or_else(|| "missing task heading".to_string())?;
+        let heading = lines[heading_index].trim();
+
+        let words: Vec<&str> = heading[4..].split_whitespace().collect();
+        if words.len() < 3 {
+            return Err(format!(
+                "invalid task heading '{}', expected '### <Type> <ID> - <subject>'",
+                heading));
+        }
+
+        let (task_type, rest) = split_word(&heading[4..]);
+        let (id_str, rest) = split_word(rest);
+        let id: u32 = match FromStr::from_str(id_str.strip_prefix('#').unwrap_or(id_str)) {
+            Ok(id) if id > 0 => id,
+            _ => return Err(format!("invalid task ID '{}', expected a positive number", id_str)),
+        };
+
+        // the subject is what follows the ID and the optional ` - `
+        let title = match split_word(rest) {
+            ("-", subject) => subject.to_string(),
+            _ => rest.to_string(),
+        };
+
+        Task::validate_subject(&title)?;
+
+        Ok(Task {
+            id,
+            title,
+            task_type: task_type.to_string(),
+            leading_notes: join_text(&lines[..heading_index]),
+            description: join_text(&lines[heading_index + 1..]),
             assignees: Vec::new(),
             properties: HashMap::new(),
+        })
+    }
+
+    /// Checks that the subject can be written in a task heading: it must not
+    /// be empty and must fit on a single line.
+    pub fn validate_subject(subject: &str) -> Result<(), String> {
+        if subject.trim().is_empty() {
+            Err("the task subject is empty".to_string())
+        } else if subject.contains('\n') || subject.contains('\r') {
+            Err(format!("the task subject '{}' spans several lines", subject.trim()))
+        } else {
+            Ok(())
         }
     }
+
+    /// Returns the parts of the task that differ from `other`. Properties are
+    /// compared as a set, so their order does not matter.
+    pub fn changed_fields(&self, other: &Task) -> Vec<TaskField> {
+        let mut fields = vec![];
+        if self.id != other.id {
+            fields.push(TaskField::Id);
+        }
+        if self.task_type != other.task_type {
+            fields.push(TaskField::Type);
+        }
+        if self.title != other.title {
+            fields.push(TaskField::Subject);
+        }
+        if self.description != other.description {
+            fields.push(TaskField::Body);
+        }
+        if self.properties != other.properties {
+            fields.push(TaskField::Properties);
+        }
+        fields
+    }
+
+    pub fn differs_from(&self, other: &Task) -> bool {
+        !self.changed_fields(other).is_empty()
+    }
+
+    /// Free text placed before the task heading, such as a note introducing
+    /// the tasks that follow.
+    pub fn leading_notes(&self) -> Option<&str> {
+        self.leading_notes.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Whether the task's type is one of the allowed types, see
+    /// `is_allowed_type`.
+    pub fn is_known_type<S: AsRef<str>>(&self, allowed: &[S]) -> bool {
+        is_allowed_type(&self.task_type, allowed)
+    }
+
+    /// Number of whitespace separated words in the task's description.
+    pub fn word_count(&self) -> usize {
+        self.description().map_or(0, |text| text.split_whitespace().count())
+    }
+
+    /// Number of characters (not bytes) in the task's description.
+    pub fn char_count(&self) -> usize {
+        self.description().map_or(0, |text| text.chars().count())
+    }
+
+    pub fn assignees(&self) -> &[Author] {
+        &self.assignees
+    }
+
+    pub fn properties(&self) -> &HashMap<String, String> {
+        &self.properties
+    }
 }
@@ -0,0 +1,88 @@
+extern crate frump;
+
+use frump::author::{find_duplicates, repair_team_line, Author};
+use frump::parser::{get_invalid_team_lines, ParseOptions};
+
+#[test]
+fn en_dash_role_separator_is_repaired() {
+    assert_eq!(repair_team_line("* John Doe <john@example.com> \u{2013} Developer"),
+               Some("* John Doe <john@example.com> - Developer".to_string()));
+}
+
+#[test]
+fn email_without_brackets_is_reported_and_repaired() {
+    let content = "## Team\n* John Doe john@example.com\n";
+    let invalid = get_invalid_team_lines(content.lines(), &ParseOptions::default());
+
+    assert_eq!(invalid, vec![(2, "* John Doe john@example.com")]);
+    assert_eq!(repair_team_line(invalid[0].1), Some("* John Doe <john@example.com>".to_string()));
+}
+
+#[test]
+fn line_without_email_is_not_repaired() {
+    assert_eq!(repair_team_line("* John Doe - Developer"), None);
+}
+
+#[test]
+fn trailing_dash_gives_no_roles() {
+    let member = Author::parse("* John Doe <john@example.com> - ").unwrap();
+    assert!(member.roles().is_empty());
+    assert_eq!(member.role(), None);
+}
+
+#[test]
+fn roles_are_matched_ignoring_case() {
+    let member = Author::parse("* John Doe <john@example.com> - Developer, Reviewer").unwrap();
+    assert!(member.has_role("reviewer"));
+    assert!(!member.has_role("Manager"));
+}
+
+#[test]
+fn email_domain_case_is_normalized() {
+    let member = Author::parse("* John <John@Corp.COM>").unwrap();
+    assert_eq!(member.normalized_email(), "John@corp.com");
+}
+
+#[test]
+fn duplicates_differ_only_in_domain_case() {
+    let lines = ["* John <John@Corp.com>", "* Johnny <John@corp.com>", "* J <john@corp.com>"];
+    let members: Vec<Author> = lines.iter().map(|line| Author::parse(line).unwrap()).collect();
+    let duplicates: Vec<&str> = find_duplicates(&members).iter().map(|m| m.name()).collect();
+
+    // the local part is case-sensitive
+    assert_eq!(duplicates, vec!["Johnny"]);
+}
+
+fn round_trip(line: &str) -> String {
+    Author::parse(line).unwrap().to_string()
+}
+
+#[test]
+fn unquoted_name_round_trips() {
+    assert_eq!(round_trip("* John Doe <john@example.com> - Developer"),
+               "* John Doe <john@example.com> - Developer");
+}
+
+#[test]
+fn quoted_name_loses_needless_quotes() {
+    let member = Author::parse("* \"John Doe\" <john@example.com>").unwrap();
+    assert_eq!(member.name(), "John Doe");
+    assert_eq!(member.to_string(), "* John Doe <john@example.com>");
+}
+
+#[test]
+fn quoted_name_with_brackets_round_trips() {
+    let line = "* \"John <JD> Doe\" <john@example.com> - Developer, Reviewer";
+    assert_eq!(Author::parse(line).unwrap().name(), "John <JD> Doe");
+    assert_eq!(round_trip(line), line);
+}
+
+#[test]
+fn dash_right_after_the_bullet_is_not_a_role_separator() {
+    assert_eq!(repair_team_line("* - Dev <a@b.com> Developer"), None);
+}
+
+#[test]
+fn bracket_before_the_email_is_not_its_end() {
+    assert_eq!(repair_team_line("* Bob - D>ev <bob@x.com> extra"), None);
+}
@@ -0,0 +1,231 @@
+extern crate frump;
+
+use frump::collection::{CollectionDiff, MergeStrategy, TaskCollection};
+use frump::task::{Task, KNOWN_TYPES};
+
+fn task(heading: &str) -> Task {
+    Task::parse(&[heading]).unwrap()
+}
+
+fn collection(headings: &[&str]) -> TaskCollection {
+    TaskCollection::new(headings.iter().map(|heading| task(heading)).collect())
+}
+
+fn ids_and_titles(tasks: &TaskCollection) -> Vec<(u32, &str)> {
+    tasks.iter().map(|task| (task.id, task.title.as_str())).collect()
+}
+
+#[test]
+fn merge_keep_mine_drops_their_conflicting_tasks() {
+    let mut mine = collection(&["### Task 1 - a"]);
+    let theirs = collection(&["### Task 1 - b", "### Task 2 - c"]);
+    mine.merge(&theirs, MergeStrategy::KeepMine).unwrap();
+    assert_eq!(ids_and_titles(&mine), vec![(1, "a"), (2, "c")]);
+}
+
+#[test]
+fn merge_keep_theirs_replaces_conflicting_tasks() {
+    let mut mine = collection(&["### Task 1 - a"]);
+    let theirs = collection(&["### Task 1 - b", "### Task 2 - c"]);
+    mine.merge(&theirs, MergeStrategy::KeepTheirs).unwrap();
+    assert_eq!(ids_and_titles(&mine), vec![(1, "b"), (2, "c")]);
+}
+
+#[test]
+fn merge_renumber_keeps_the_ids_without_conflict() {
+    let mut mine = collection(&["### Task 1 - a"]);
+    let theirs = collection(&["### Task 1 - b", "### Task 2 - c"]);
+    mine.merge(&theirs, MergeStrategy::Renumber).unwrap();
+    assert_eq!(ids_and_titles(&mine), vec![(1, "a"), (3, "b"), (2, "c")]);
+}
+
+#[test]
+fn stats_count_tasks_by_type() {
+    let tasks = collection(&["### Task 1 - a", "### Bug 2 - b", "### Task 3 - c"]);
+    let stats = tasks.stats();
+
+    assert_eq!(stats.total, 3);
+    assert_eq!(stats.by_type.get("Task"), Some(&2));
+    assert_eq!(stats.by_type.get("Bug"), Some(&1));
+    assert_eq!((stats.no_status, stats.unassigned), (3, 3));
+}
+
+#[test]
+fn stats_of_several_collections_add_up() {
+    let mut stats = collection(&["### Task 1 - a", "### Bug 2 - b"]).stats();
+    stats.add(&collection(&["### Task 1 - c"]).stats());
+
+    assert_eq!(stats.total, 3);
+    assert_eq!(stats.by_type.get("Task"), Some(&2));
+    assert_eq!(stats.unassigned, 3);
+}
+
+#[test]
+fn stats_measure_the_bodies() {
+    let tasks = TaskCollection::new(vec![
+        Task::parse(&["### Task 1 - a", "two wörds"]).unwrap(),
+        Task::parse(&["### Task 2 - b", "three words", "here"]).unwrap(),
+        task("### Task 3 - c"),
+    ]);
+    let stats = tasks.stats();
+
+    assert_eq!(stats.empty_body, 1);
+    assert_eq!(stats.body_words, 5);
+    assert_eq!(stats.body_chars, 9 + 16);
+    assert_eq!(stats.average_body_words(), 5.0 / 3.0);
+    assert_eq!(stats.largest_task, Some(("Task 2 - b".to_string(), 3)));
+}
+
+#[test]
+fn update_tells_whether_the_task_exists() {
+    let mut tasks = collection(&["### Task 1 - a"]);
+
+    assert!(tasks.update(1, |task| task.title = "b".to_string()));
+    assert!(!tasks.update(2, |task| task.title = "c".to_string()));
+    assert_eq!(ids_and_titles(&tasks), vec![(1, "b")]);
+}
+
+fn types(tasks: &TaskCollection) -> Vec<&str> {
+    tasks.iter().map(|task| task.task_type.as_str()).collect()
+}
+
+#[test]
+fn retype_custom_to_custom() {
+    let mut tasks = collection(&["### Chore 1 - a", "### Task 2 - b", "### chore 3 - c"]);
+    assert_eq!(tasks.retype("Chore", "Maintenance"), 2);
+    assert_eq!(types(&tasks), vec!["Maintenance", "Task", "Maintenance"]);
+}
+
+#[test]
+fn retype_custom_to_standard() {
+    let mut tasks = collection(&["### Defect 1 - a", "### Bug 2 - b"]);
+    assert_eq!(tasks.retype("Defect", "Bug"), 1);
+    assert_eq!(types(&tasks), vec!["Bug", "Bug"]);
+    assert_eq!(tasks.retype("Chore", "Task"), 0);
+}
+
+#[test]
+fn partitions_are_disjoint_and_cover_all_tasks() {
+    let tasks = collection(&["### Task 1 - a", "### Bug 2 - b", "### Task 3 - c", "### Issue 4 - d"]);
+    let (tasks_of_type, others) = tasks.partition(|task| task.task_type == "Task");
+
+    let ids = |tasks: &[&Task]| tasks.iter().map(|task| task.id).collect::<Vec<_>>();
+    assert_eq!(ids(&tasks_of_type), vec![1, 3]);
+    assert_eq!(ids(&others), vec![2, 4]);
+}
+
+fn with_ids(ids: &[u32]) -> TaskCollection {
+    TaskCollection::new(ids.iter().map(|id| task(&format!("### Task {} - t", id))).collect())
+}
+
+#[test]
+fn contiguous_ids_have_no_gaps() {
+    assert!(with_ids(&[1, 2, 3]).id_gaps().is_empty());
+    assert!(with_ids(&[]).id_gaps().is_empty());
+}
+
+#[test]
+fn single_missing_id_is_a_gap() {
+    assert_eq!(with_ids(&[1, 3]).id_gaps(), vec![2..=2]);
+    assert_eq!(with_ids(&[2, 3]).id_gaps(), vec![1..=1]);
+}
+
+#[test]
+fn several_missing_ids_are_one_gap() {
+    assert_eq!(with_ids(&[5, 1, 9]).id_gaps(), vec![2..=4, 6..=8]);
+}
+
+#[test]
+fn biggest_id_does_not_overflow() {
+    assert_eq!(with_ids(&[1, u32::MAX]).id_gaps(), vec![2..=u32::MAX - 1]);
+}
+
+fn found_ids(tasks: &TaskCollection, query: &str) -> Vec<u32> {
+    tasks.find_by_subject(query).iter().map(|task| task.id).collect()
+}
+
+#[test]
+fn find_by_subject_with_zero_one_and_many_matches() {
+    let tasks = collection(&["### Task 1 - write docs", "### Task 2 - Fix the parser",
+                             "### Bug 3 - parser panics"]);
+
+    assert_eq!(found_ids(&tasks, "search"), Vec::<u32>::new());
+    assert_eq!(found_ids(&tasks, "DOCS"), vec![1]);
+    assert_eq!(found_ids(&tasks, "parser"), vec![2, 3]);
+}
+
+#[test]
+fn find_by_subject_with_blank_query_matches_nothing() {
+    let tasks = collection(&["### Task 1 - write docs"]);
+    assert!(tasks.find_by_subject("").is_empty());
+    assert!(tasks.find_by_subject("  ").is_empty());
+}
+
+#[test]
+fn next_id_fill_gaps_returns_the_lowest_unused_id() {
+    assert_eq!(with_ids(&[1, 3, 4]).next_id_fill_gaps(), Ok(2));
+    assert_eq!(with_ids(&[1, 3, 4]).next_id(), Ok(5));
+    assert_eq!(with_ids(&[1, 2]).next_id_fill_gaps(), Ok(3));
+    assert_eq!(with_ids(&[]).next_id_fill_gaps(), Ok(1));
+}
+
+#[test]
+fn next_id_after_the_biggest_id_fails() {
+    assert!(with_ids(&[u32::MAX]).next_id().is_err());
+    assert_eq!(with_ids(&[1, u32::MAX]).next_id_fill_gaps(), Ok(2));
+    assert!(with_ids(&[u32::MAX]).next_id_fill_gaps().is_ok());
+}
+
+#[test]
+fn merge_without_ids_left_to_renumber_fails_unchanged() {
+    let mut mine = with_ids(&[1, u32::MAX - 1]);
+    let theirs = with_ids(&[1, 2, u32::MAX]);
+
+    assert!(mine.merge(&theirs, MergeStrategy::Renumber).is_err());
+    assert_eq!(mine.iter().map(|task| task.id).collect::<Vec<_>>(), vec![1, u32::MAX - 1]);
+    assert!(mine.merge(&theirs, MergeStrategy::KeepMine).is_ok());
+}
+
+#[test]
+fn renumber_sequential_returns_the_changed_ids() {
+    let mut tasks = with_ids(&[1, 4, 2, 9]);
+
+    assert_eq!(tasks.renumber_sequential(1), Ok(vec![(4, 2), (2, 3), (9, 4)]));
+    assert_eq!(tasks.iter().map(|task| task.id).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    assert_eq!(tasks.renumber_sequential(1), Ok(vec![]));
+}
+
+#[test]
+fn renumber_sequential_rejects_0_and_overflow() {
+    let mut tasks = with_ids(&[1, 2]);
+
+    assert!(tasks.renumber_sequential(0).is_err());
+    assert!(tasks.renumber_sequential(u32::MAX).is_err());
+    assert_eq!(tasks.renumber_sequential(u32::MAX - 1), Ok(vec![(1, u32::MAX - 1), (2, u32::MAX)]));
+}
+
+#[test]
+fn diff_reports_added_removed_and_changed_ids() {
+    let old = collection(&["### Task 1 - a", "### Task 2 - b", "### Task 3 - c"]);
+    let new = collection(&["### Task 1 - a", "### Bug 3 - c", "### Task 4 - d"]);
+
+    assert_eq!(old.diff(&new), CollectionDiff { added: vec![4], removed: vec![2], changed: vec![3] });
+    assert_eq!(old.diff(&old), CollectionDiff::default());
+}
+
+#[test]
+fn diff_reports_a_task_differing_only_in_body() {
+    let old = TaskCollection::new(vec![Task::parse(&["### Task 1 - a", "body"]).unwrap()]);
+    let new = TaskCollection::new(vec![Task::parse(&["### Task 1 - a", "new body"]).unwrap()]);
+
+    assert_eq!(old.diff(&new).changed, vec![1]);
+}
+
+#[test]
+fn with_unknown_type_finds_the_typos() {
+    let tasks = collection(&["### Task 1 - a", "### tsak 2 - b", "### BUG 3 - c"]);
+    let unknown: Vec<u32> = tasks.with_unknown_type(KNOWN_TYPES).iter().map(|task| task.id).collect();
+
+    assert_eq!(unknown, vec![2]);
+    assert!(tasks.with_unknown_type::<String>(&[]).is_empty());
+}
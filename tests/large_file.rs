@@ -0,0 +1,70 @@
+extern crate frump;
+
+use std::cmp;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+
+use frump::parser::{parse_tasks, ParseOptions};
+
+const TASK_COUNT: usize = 200_000;
+
+/// A frump file of `TASK_COUNT` tasks, generated while it is read so it is
+/// never held in memory as a whole.
+struct GeneratedFile {
+    next_task: usize,
+    pending: Vec<u8>,
+}
+
+impl Read for GeneratedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            if self.next_task > TASK_COUNT {
+                return Ok(0);
+            }
+            let text =
+                if self.next_task == 0 {
+                    "# Project\n\n## Tasks\n".to_string()
+                } else {
+                    format!("### Task {} - generated task\n{}\n\n", self.next_task,
+                            "Some body text of the generated task. ".repeat(5))
+                };
+            self.pending = text.into_bytes();
+            self.next_task += 1;
+        }
+
+        let count = cmp::min(buf.len(), self.pending.len());
+        buf[..count].copy_from_slice(&self.pending[..count]);
+        self.pending.drain(..count);
+        Ok(count)
+    }
+}
+
+/// The peak resident memory of the process in kB, where the system tells it.
+fn peak_memory() -> Option<usize> {
+    let mut status = String::new();
+    File::open("/proc/self/status").ok()?.read_to_string(&mut status).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Streams about 40 MB of tasks and checks that the memory used stays far
+/// below that. Run it alone with `cargo test --release -- --ignored`.
+#[test]
+#[ignore]
+fn streaming_a_large_file_keeps_memory_low() {
+    let before = peak_memory();
+    let file = GeneratedFile { next_task: 0, pending: vec![] };
+
+    let mut count = 0;
+    for task in parse_tasks(BufReader::new(file), &ParseOptions::default()) {
+        let task = task.unwrap();
+        count += 1;
+        assert_eq!(task.id as usize, count);
+    }
+    assert_eq!(count, TASK_COUNT);
+
+    if let (Some(before), Some(after)) = (before, peak_memory()) {
+        println!("peak memory grew by {} kB", after - before);
+        assert!(after - before < 8 * 1024, "peak memory grew by {} kB", after - before);
+    }
+}
@@ -0,0 +1,292 @@
+extern crate frump;
+
+use frump::parser::{get_malformed_headings, parse, parse_lenient, parse_single_task, parse_tasks,
+                    parse_team, ParseErrorKind, ParseOptions};
+
+fn titles(content: &str) -> Vec<String> {
+    let tasks = parse(content, &ParseOptions::default()).unwrap();
+    tasks.iter().map(|task| task.title.clone()).collect()
+}
+
+#[test]
+fn unrelated_level_3_heading_stays_in_the_body() {
+    let content = "## Tasks\n\
+                   ### Task 1 - a\n\
+                   ### Details\n\
+                   more text\n\
+                   ### Task 2 - b\n";
+    let tasks = parse(content, &ParseOptions::default()).unwrap();
+    let tasks: Vec<_> = tasks.iter().collect();
+
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].description(), Some("### Details\nmore text"));
+    assert_eq!(tasks[1].title, "b");
+}
+
+#[test]
+fn headings_without_an_id_are_body_with_a_warning() {
+    for heading in &["### Task - forgot id", "### Bug5 - x", "### Task x - y"] {
+        let content = format!("## Tasks\n### Task 1 - a\n{}\n", heading);
+        let options = ParseOptions::default();
+        let tasks = parse(&content, &options).unwrap();
+
+        assert_eq!(tasks.iter().next().unwrap().description(), Some(*heading));
+        assert_eq!(get_malformed_headings(content.lines(), &options, &["Task", "Bug"]),
+                   vec![(3, *heading)]);
+    }
+}
+
+#[test]
+fn body_headings_with_numbers_or_dashes_are_not_task_headings() {
+    for heading in &["### Step 1: download", "### Q&A - open points"] {
+        let content = format!("## Tasks\n### Task 1 - a\n{}\n### Task 2 - b\n", heading);
+        let options = ParseOptions::default();
+        let tasks = parse(&content, &options).unwrap();
+        let tasks: Vec<_> = tasks.iter().collect();
+
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].description(), Some(*heading));
+        assert!(get_malformed_headings(content.lines(), &options, &["Task", "Bug"]).is_empty());
+    }
+}
+
+#[test]
+fn heading_with_hash_id_is_a_task_heading() {
+    let tasks = parse("## Tasks\n### Task #5 - a\n", &ParseOptions::default()).unwrap();
+    assert_eq!(tasks.iter().next().unwrap().id, 5);
+}
+
+#[test]
+fn heading_without_separator_still_parses() {
+    assert_eq!(titles("## Tasks\n### Task 1 subject\n"), vec!["subject"]);
+}
+
+#[test]
+fn headings_inside_code_blocks_stay_in_the_body() {
+    let content = "## Tasks\n\
+                   ### Task 1 - a\n\
+                   ```\n\
+                   ### Task 9 - example\n\
+                   ## Team\n\
+                   ```\n\
+                   \n    ### Task 8 - indented example\n\
+                   ### Task 2 - b\n";
+    assert_eq!(titles(content), vec!["a", "b"]);
+}
+
+#[test]
+fn unclosed_code_block_is_reported() {
+    let content = "## Tasks\n\
+                   ### Task 1 - a\n\
+                   ```\n\
+                   ### Task 2 - b\n\
+                   ### Task 3 - c\n";
+    let (_, errors) = parse_lenient(content, &ParseOptions::default());
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, ParseErrorKind::UnclosedBlock);
+    assert_eq!(errors[0].line, 3);
+}
+
+#[test]
+fn task_markers_keep_any_heading_in_the_body() {
+    let content = "## Tasks\n\
+                   ### Task 1 - a\n\
+                   <!-- frump:task -->\n\
+                   ## Subheading\n\
+                   ## Team\n\
+                   * X <x@y>\n\
+                   <!-- /frump:task -->\n\
+                   ### Task 2 - b\n\
+                   ## Team\n\
+                   * John Doe <john@example.com>\n";
+    let options = ParseOptions::default();
+    let tasks = parse(content, &options).unwrap();
+    let tasks: Vec<_> = tasks.iter().collect();
+
+    assert_eq!(tasks.len(), 2);
+    assert!(tasks[0].description().unwrap().contains("## Subheading"));
+    let team: Vec<String> = parse_team(content, &options).iter().map(|m| m.to_string()).collect();
+    assert_eq!(team, vec!["* John Doe <john@example.com>"]);
+}
+
+#[test]
+fn team_heading_in_a_code_block_is_not_the_team_section() {
+    let content = "## Tasks\n\
+                   ### Task 1 - a\n\
+                   ```\n\
+                   ## Team\n\
+                   * X <x@y>\n\
+                   ```\n";
+    assert!(parse_team(content, &ParseOptions::default()).is_empty());
+}
+
+#[test]
+fn unclosed_task_markers_are_reported() {
+    let content = "## Tasks\n\
+                   ### Task 1 - a\n\
+                   <!-- frump:task -->\n\
+                   ### Task 2 - b\n";
+    let (tasks, errors) = parse_lenient(content, &ParseOptions::default());
+
+    assert_eq!(tasks.iter().count(), 1);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, ParseErrorKind::UnclosedBlock);
+    assert_eq!(errors[0].line, 3);
+}
+
+#[test]
+fn merge_conflict_is_reported() {
+    let content = "## Tasks\n\
+                   <<<<<<< HEAD\n\
+                   ### Task 1 - a\n\
+                   =======\n\
+                   ### Task 1 - b\n\
+                   >>>>>>> branch\n";
+    let error = parse(content, &ParseOptions::default()).unwrap_err();
+
+    assert_eq!(error.kind, ParseErrorKind::MergeConflict);
+    assert_eq!(error.line, 2);
+}
+
+#[test]
+fn merge_conflict_markers_in_a_code_block_are_body() {
+    let content = "## Tasks\n\
+                   ### Task 1 - document conflicts\n\
+                   ```\n\
+                   <<<<<<< HEAD\n\
+                   =======\n\
+                   >>>>>>> branch\n\
+                   ```\n";
+    assert_eq!(titles(content), vec!["document conflicts"]);
+}
+
+#[test]
+fn single_task_block_is_parsed() {
+    let task = parse_single_task("### Task 2 - b\nbody\n```\n### Task 3 - example\n```").unwrap();
+    assert_eq!((task.id, task.title.as_str()), (2, "b"));
+}
+
+#[test]
+fn single_task_block_with_two_tasks_fails() {
+    let error = parse_single_task("### Task 1 - a\nbody\n### Task 2 - b").unwrap_err();
+    assert_eq!((error.kind, error.line), (ParseErrorKind::InvalidTask, 3));
+
+    let error = parse_single_task("### Task 1 - a\n## Notes\n### Task 2 - b").unwrap_err();
+    assert_eq!((error.kind, error.line), (ParseErrorKind::InvalidTask, 3));
+}
+
+#[test]
+fn text_around_the_tasks_is_not_lost() {
+    let content = "## Tasks\n\
+                   Notes before the first task.\n\
+                   \n\
+                   ### Task 1 - a\n\
+                   Text between the tasks.\n\
+                   ### Task 2 - b\n";
+    let tasks = parse(content, &ParseOptions::default()).unwrap();
+    let tasks: Vec<_> = tasks.iter().collect();
+
+    assert_eq!(tasks[0].leading_notes(), Some("Notes before the first task."));
+    assert_eq!(tasks[0].description(), Some("Text between the tasks."));
+    assert_eq!(tasks[1].leading_notes(), None);
+}
+
+#[test]
+fn byte_order_mark_and_crlf_are_ignored() {
+    let content = "\u{FEFF}# T\r\n\r\n## Tasks\r\n\r\n### Task 1 - x\r\n";
+    let tasks = parse(content, &ParseOptions::default()).unwrap();
+    let task = tasks.iter().next().unwrap();
+
+    assert_eq!((task.task_type.as_str(), task.id, task.title.as_str()), ("Task", 1, "x"));
+    assert_eq!(task.leading_notes(), None);
+}
+
+#[test]
+fn byte_order_mark_does_not_hide_the_first_heading() {
+    assert_eq!(titles("\u{FEFF}## Tasks\r\n### Task 1 - x\r\nbody\r\n"), vec!["x"]);
+}
+
+/// Parses the content with `parse_tasks` and with `parse_lenient`, returning
+/// both results in the same form.
+fn parse_both_ways(content: &str) -> (String, String) {
+    let options = ParseOptions::default();
+
+    let mut tasks = vec![];
+    let mut errors = vec![];
+    for result in parse_tasks(content.as_bytes(), &options) {
+        match result {
+            Ok(task) => tasks.push(task),
+            Err(error) => errors.push(error),
+        }
+    }
+    let streamed = format!("{:?} {:?}", tasks, errors);
+
+    let (tasks, errors) = parse_lenient(content, &options);
+    let tasks: Vec<_> = tasks.iter().collect();
+    (streamed, format!("{:?} {:?}", tasks, errors))
+}
+
+#[test]
+fn streaming_parse_matches_parse_lenient() {
+    let contents = [
+        // leading notes, fences and markers
+        "## Tasks\n\
+         Notes.\n\
+         ### Task 1 - a\n\
+         ```\n\
+         ### Task 9 - example\n\
+         ```\n\
+         ### Task 2 - b\n\
+         <!-- frump:task -->\n\
+         ## Details\n\
+         <!-- /frump:task -->\n\
+         ### Task 3\n\
+         ## Team\n\
+         * John Doe <john@example.com>\n",
+        // a merge conflict
+        "## Tasks\n\
+         ### Task 1 - a\n\
+         <<<<<<< HEAD\n\
+         ### Task 2 - b\n\
+         =======\n\
+         ### Task 2 - c\n\
+         >>>>>>> branch\n",
+        // an unclosed code block
+        "## Tasks\n\
+         ### Task 1 - a\n\
+         ```\n\
+         ### Task 2 - b\n",
+    ];
+
+    for content in &contents {
+        let (streamed, parsed) = parse_both_ways(content);
+        assert_eq!(streamed, parsed);
+    }
+}
+
+#[test]
+fn custom_section_headings() {
+    let content = "## Backlog\n\
+                   ### Task 1 - a\n\
+                   ## Background\n\
+                   ### Task 2 - not a task\n\
+                   ## People\n\
+                   * John Doe <john@example.com>\n";
+    let options = ParseOptions {
+        tasks_heading: "backlog".to_string(),
+        team_heading: "People".to_string(),
+    };
+
+    let tasks = parse(content, &options).unwrap();
+    assert_eq!(tasks.iter().map(|task| task.id).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(parse_team(content, &options).len(), 1);
+    assert!(parse(content, &ParseOptions::default()).unwrap().iter().next().is_none());
+}
+
+#[test]
+fn section_heading_must_match_as_a_whole() {
+    let content = "## Background\n### Task 1 - a\n";
+    let options = ParseOptions { tasks_heading: "Back".to_string(), ..ParseOptions::default() };
+    assert!(parse(content, &options).unwrap().iter().next().is_none());
+}
@@ -0,0 +1,71 @@
+extern crate frump;
+
+use frump::render::{render_tasks, ListFormat};
+use frump::task::Task;
+
+#[test]
+fn porcelain_has_one_tab_separated_line_per_task() {
+    let a = Task::parse(&["### Task 1 - a"]).unwrap();
+    let b = Task::parse(&["### Bug 2 - b"]).unwrap();
+
+    assert_eq!(render_tasks(&[&a, &b], None, ListFormat::Porcelain),
+               "1\tTask\t\t\ta\n2\tBug\t\t\tb\n");
+}
+
+#[test]
+fn porcelain_replaces_tabs_in_fields() {
+    let task = Task::parse(&["### Task 1 - a\tsubject\twith tabs"]).unwrap();
+
+    assert_eq!(render_tasks(&[&task], Some("my\tfile.md"), ListFormat::Porcelain),
+               "my file.md:1\tTask\t\t\ta subject with tabs\n");
+}
+
+#[test]
+fn oneline_shows_the_id_type_and_subject() {
+    let task = Task::parse(&["### Bug 5 - subject", "body"]).unwrap();
+
+    assert_eq!(render_tasks(&[&task], None, ListFormat::Oneline), "[5] Bug: subject\n");
+    assert_eq!(render_tasks(&[&task], Some("a.md"), ListFormat::Oneline),
+               "[a.md:5] Bug: subject\n");
+}
+
+#[test]
+fn full_cuts_the_body_snippet() {
+    let long_line = "x".repeat(80);
+    let short = Task::parse(&["### Task 1 - a", "", "first line", "second line"]).unwrap();
+    let long = Task::parse(&["### Task 2 - b", &long_line]).unwrap();
+    let empty = Task::parse(&["### Task 3 - c"]).unwrap();
+
+    assert_eq!(render_tasks(&[&short, &long, &empty], None, ListFormat::Full),
+               format!("Task 1 - a\n    first line\nTask 2 - b\n    {}...\nTask 3 - c\n",
+                       "x".repeat(72)));
+}
+
+#[test]
+fn full_keeps_a_body_line_of_exactly_the_snippet_length() {
+    let line = "é".repeat(72);
+    let task = Task::parse(&["### Task 1 - a", &line]).unwrap();
+
+    assert_eq!(render_tasks(&[&task], None, ListFormat::Full),
+               format!("Task 1 - a\n    {}\n", line));
+}
+
+#[test]
+fn table_columns_fit_the_content() {
+    let a = Task::parse(&["### Task 1 - a"]).unwrap();
+    let b = Task::parse(&["### Maintenance 12345 - b"]).unwrap();
+
+    assert_eq!(render_tasks(&[&a, &b], None, ListFormat::Table),
+               "ID     Type         Subject\n\
+                1      Task         a\n\
+                12345  Maintenance  b\n");
+}
+
+#[test]
+fn table_columns_fit_qualified_ids() {
+    let a = Task::parse(&["### Bug 1 - a"]).unwrap();
+
+    assert_eq!(render_tasks(&[&a], Some("frump.md"), ListFormat::Table),
+               "ID          Type  Subject\n\
+                frump.md:1  Bug   a\n");
+}
@@ -0,0 +1,40 @@
+extern crate frump;
+
+use frump::parser::{parse, ParseOptions};
+use frump::search::{rank, score};
+
+const CONTENT: &str = "## Tasks\n\
+                       ### Task 1 - write the docs\n\
+                       ### Bug 2 - parser panics on empty file\n\
+                       The parser should report the problem instead.\n\
+                       ### Task 3 - add a search command\n";
+
+fn ids(matches: &[(u32, f32)]) -> Vec<u32> {
+    matches.iter().map(|&(id, _)| id).collect()
+}
+
+#[test]
+fn exact_substring_scores_highest() {
+    assert_eq!(score("Search", "add a search command"), 1.0);
+    assert!(score("serch", "add a search command") < 1.0);
+}
+
+#[test]
+fn rank_tolerates_typos() {
+    let tasks = parse(CONTENT, &ParseOptions::default()).unwrap();
+    assert_eq!(ids(&rank("serch comand", &tasks, false)), vec![3]);
+}
+
+#[test]
+fn rank_searches_the_description_when_full() {
+    let tasks = parse(CONTENT, &ParseOptions::default()).unwrap();
+    assert!(rank("report the problem", &tasks, false).is_empty());
+    assert_eq!(ids(&rank("report the problem", &tasks, true)), vec![2]);
+}
+
+#[test]
+fn blank_query_matches_nothing() {
+    let tasks = parse(CONTENT, &ParseOptions::default()).unwrap();
+    assert!(rank("", &tasks, true).is_empty());
+    assert!(rank("   ", &tasks, true).is_empty());
+}
@@ -0,0 +1,84 @@
+extern crate frump;
+
+use frump::task::{parse_id, Task, TaskField, KNOWN_TYPES};
+
+#[test]
+fn subject_is_the_text_after_the_id() {
+    let cases = [
+        ("### Task 5 - subject", "Task", "subject"),
+        ("### Task 5 subject - with dash", "Task", "subject - with dash"),
+        ("### Sub-task 5 - subj", "Sub-task", "subj"),
+        ("### 5é 5 subj", "5é", "subj"),
+        ("### Task 5 -5 degrees", "Task", "-5 degrees"),
+    ];
+    for &(heading, task_type, title) in &cases {
+        let task = Task::parse(&[heading]).unwrap();
+        assert_eq!((task.task_type.as_str(), task.id, task.title.as_str()), (task_type, 5, title));
+    }
+}
+
+#[test]
+fn empty_subject_is_rejected() {
+    assert!(Task::validate_subject("").is_err());
+    assert!(Task::validate_subject("   ").is_err());
+    assert!(Task::parse(&["### Task 5 - "]).is_err());
+}
+
+#[test]
+fn multiline_subject_is_rejected() {
+    assert!(Task::validate_subject("first\nsecond").is_err());
+    assert!(Task::validate_subject("first\rsecond").is_err());
+    assert!(Task::validate_subject("single line").is_ok());
+}
+
+#[test]
+fn leading_notes_are_not_part_of_the_task() {
+    let a = Task::parse(&["A note.", "### Task 1 - a", "body"]).unwrap();
+    let b = Task::parse(&["### Task 1 - a", "body"]).unwrap();
+
+    assert_eq!(a, b);
+    assert!(a.changed_fields(&b).is_empty());
+}
+
+#[test]
+fn changed_fields_tell_the_body_from_the_subject() {
+    let a = Task::parse(&["### Task 1 - a", "body"]).unwrap();
+
+    let b = Task::parse(&["### Task 1 - a", "other body"]).unwrap();
+    assert_eq!(a.changed_fields(&b), vec![TaskField::Body]);
+    assert!(a.differs_from(&b));
+
+    let b = Task::parse(&["### Task 1 - b", "body"]).unwrap();
+    assert_eq!(a.changed_fields(&b), vec![TaskField::Subject]);
+
+    let b = Task::parse(&["### Bug 2 - b"]).unwrap();
+    assert_eq!(a.changed_fields(&b), vec![TaskField::Id, TaskField::Type, TaskField::Subject,
+                                          TaskField::Body]);
+}
+
+#[test]
+fn parse_id_accepts_prefixed_ids() {
+    assert_eq!(parse_id("5", None), Ok(5));
+    assert_eq!(parse_id(" #5 ", None), Ok(5));
+    assert_eq!(parse_id("T-5", Some("T-")), Ok(5));
+    assert_eq!(parse_id("#5", Some("T-")), Ok(5));
+}
+
+#[test]
+fn parse_id_rejects_what_is_not_a_number() {
+    for text in &["", "#", "abc", "0", "+5", "T-#5", "T-5", "5x", "99999999999"] {
+        assert!(parse_id(text, None).is_err(), "{}", text);
+    }
+    assert!(parse_id("T-#5", Some("T-")).is_err());
+    assert!(parse_id("T-", Some("T-")).is_err());
+}
+
+#[test]
+fn known_types_are_matched_ignoring_case() {
+    let task = Task::parse(&["### bug 1 - a"]).unwrap();
+
+    assert!(task.is_known_type(KNOWN_TYPES));
+    assert!(task.is_known_type(&["Bug".to_string()]));
+    assert!(!task.is_known_type(&["Task"]));
+    assert!(task.is_known_type::<&str>(&[]));
+}